@@ -21,7 +21,7 @@
  * for more details.
 */
 
-use std::time::Duration;
+use std::{cell::Cell, time::Duration};
 
 use directory::core::config::ConfigDirectory;
 use imap::core::{ImapSessionManager, IMAP};
@@ -42,6 +42,10 @@ use jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
+thread_local! {
+    static IN_PANIC_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
 #[tokio::main]
 async fn main() -> std::io::Result<()> {
     let mut config = Config::init();
@@ -56,6 +60,31 @@ async fn main() -> std::io::Result<()> {
     )
     .failed("Failed to enable tracing");
 
+    // Log panics
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !IN_PANIC_HOOK.with(|in_hook| in_hook.replace(true)) {
+            let reason = info
+                .payload()
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| info.payload().downcast_ref::<String>().map(|s| s.as_str()))
+                .unwrap_or("unknown");
+            tracing::error!(
+                context = "panic",
+                event = "error",
+                location = info
+                    .location()
+                    .map(|location| location.to_string())
+                    .unwrap_or_default(),
+                "Panic: {}",
+                reason
+            );
+            IN_PANIC_HOOK.with(|in_hook| in_hook.set(false));
+        }
+        default_hook(info);
+    }));
+
     // Bind ports and drop privileges
     let mut servers = config.parse_servers().failed("Invalid configuration");
     servers.bind(&config);